@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Lifecycle of a single proxy's circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are skipped until the cooldown elapses.
+    Open,
+    /// A single trial request is permitted to probe recovery.
+    HalfOpen,
+}
+
+/// Per-proxy breaker bookkeeping.
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl Default for BreakerState {
+    fn default() -> Self {
+        BreakerState {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+}
+
+/// A set of per-proxy circuit breakers shared across all tasks.
+///
+/// After `failure_threshold` consecutive failures a proxy trips to `Open` and
+/// is skipped until `cooldown` elapses, at which point one trial request is
+/// allowed (`HalfOpen`). A success closes the breaker; a failure re-opens it
+/// with a fresh timer.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    states: Arc<Mutex<HashMap<String, BreakerState>>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            states: Arc::new(Mutex::new(HashMap::new())),
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    /// Whether a request may currently be sent through `proxy`. Transitions an
+    /// `Open` breaker to `HalfOpen` once its cooldown has elapsed. A `HalfOpen`
+    /// breaker admits one trial per cooldown window until an outcome is recorded.
+    pub fn allows(&self, proxy: &str) -> bool {
+        let mut states = self.states.lock().unwrap();
+        let entry = states.entry(proxy.to_string()).or_default();
+        match entry.state {
+            CircuitState::Closed => true,
+            CircuitState::Open | CircuitState::HalfOpen => match entry.opened_at {
+                Some(opened) if opened.elapsed() >= self.cooldown => {
+                    entry.state = CircuitState::HalfOpen;
+                    entry.opened_at = Some(Instant::now());
+                    true
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Record a successful request, closing the breaker.
+    pub fn record_success(&self, proxy: &str) {
+        let mut states = self.states.lock().unwrap();
+        let entry = states.entry(proxy.to_string()).or_default();
+        entry.state = CircuitState::Closed;
+        entry.consecutive_failures = 0;
+        entry.opened_at = None;
+    }
+
+    /// Record a failed request, tripping the breaker once the consecutive
+    /// failure count reaches the threshold (or immediately from `HalfOpen`).
+    pub fn record_failure(&self, proxy: &str) {
+        let mut states = self.states.lock().unwrap();
+        let entry = states.entry(proxy.to_string()).or_default();
+        entry.consecutive_failures += 1;
+        if entry.state == CircuitState::HalfOpen
+            || entry.consecutive_failures >= self.failure_threshold
+        {
+            entry.state = CircuitState::Open;
+            entry.opened_at = Some(Instant::now());
+        }
+    }
+}
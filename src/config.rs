@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use serde::Deserialize;
+
+use crate::results::OutputFormat;
+
+/// Runtime configuration for the checker.
+///
+/// Values are layered: built-in defaults, overridden by an optional TOML
+/// config file, overridden in turn by any CLI flags that are set. This lets
+/// the tool target different KiloEx reward flows or hosts without recompiling.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Path to the newline-delimited list of addresses to query.
+    pub addresses_path: String,
+    /// Path to the newline-delimited list of `host:port` or
+    /// `user:pass@host:port` proxies (each is used as an `http://` proxy URL).
+    pub proxies_path: String,
+    /// Maximum retries per address before giving up.
+    pub max_retries: u32,
+    /// Maximum number of in-flight requests.
+    pub max_concurrency: usize,
+    /// Sustained request rate (tokens per second) for the rate limiter.
+    pub rate_per_sec: u32,
+    /// Burst ceiling for the rate limiter.
+    pub burst: u32,
+    /// Consecutive failures before a proxy's circuit breaker trips.
+    pub failure_threshold: u32,
+    /// How long a tripped proxy stays Open before a trial request, in seconds.
+    pub cooldown_secs: u64,
+    /// Path to the incremental, resumable results file.
+    pub output_path: String,
+    /// Results serialization format.
+    pub output_format: OutputFormat,
+    /// Per-request timeout, in seconds.
+    pub request_timeout_secs: u64,
+    /// Base API endpoint, without query parameters.
+    pub api_base: String,
+    /// Value of the `type` query parameter selecting the reward flow.
+    pub api_type: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            addresses_path: "data/addresses.txt".to_string(),
+            proxies_path: "data/proxies.txt".to_string(),
+            max_retries: 10,
+            max_concurrency: 32,
+            rate_per_sec: 16,
+            burst: 32,
+            failure_threshold: 5,
+            cooldown_secs: 30,
+            output_path: "data/results.json".to_string(),
+            output_format: OutputFormat::Json,
+            request_timeout_secs: 30,
+            api_base: "https://opapi.kiloex.io/point/queryKiloAccountAwardFlow".to_string(),
+            api_type: 0,
+        }
+    }
+}
+
+impl Config {
+    /// Build the effective config by reading the optional TOML file and then
+    /// applying CLI overrides.
+    pub fn load(cli: Cli) -> anyhow::Result<Config> {
+        let mut config = match &cli.config {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("reading config file {}", path.display()))?;
+                toml::from_str(&contents)
+                    .with_context(|| format!("parsing config file {}", path.display()))?
+            }
+            None => Config::default(),
+        };
+
+        if let Some(v) = cli.addresses {
+            config.addresses_path = v;
+        }
+        if let Some(v) = cli.proxies {
+            config.proxies_path = v;
+        }
+        if let Some(v) = cli.max_retries {
+            config.max_retries = v;
+        }
+        if let Some(v) = cli.concurrency {
+            config.max_concurrency = v;
+        }
+        if let Some(v) = cli.rate {
+            config.rate_per_sec = v;
+        }
+        if let Some(v) = cli.burst {
+            config.burst = v;
+        }
+        if let Some(v) = cli.format {
+            config.output_format = v;
+        }
+        if let Some(v) = cli.output {
+            config.output_path = v;
+        }
+        if let Some(v) = cli.timeout {
+            config.request_timeout_secs = v;
+        }
+        if let Some(v) = cli.api_base {
+            config.api_base = v;
+        }
+        if let Some(v) = cli.api_type {
+            config.api_type = v;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Command-line overrides for configured values. The circuit-breaker settings
+/// (`failure_threshold`, `cooldown_secs`) have no flag and are set only
+/// through the config file.
+#[derive(Debug, Parser)]
+#[command(about = "Check KiloEx airdrop amounts across many addresses")]
+pub struct Cli {
+    /// Path to a TOML config file to load before applying flags.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Addresses file path.
+    #[arg(long)]
+    pub addresses: Option<String>,
+    /// Proxies file path.
+    #[arg(long)]
+    pub proxies: Option<String>,
+    /// Maximum retries per address.
+    #[arg(long)]
+    pub max_retries: Option<u32>,
+    /// Maximum in-flight requests.
+    #[arg(long)]
+    pub concurrency: Option<usize>,
+    /// Sustained request rate per second.
+    #[arg(long)]
+    pub rate: Option<u32>,
+    /// Rate-limiter burst ceiling.
+    #[arg(long)]
+    pub burst: Option<u32>,
+    /// Results format: json or csv.
+    #[arg(long)]
+    pub format: Option<OutputFormat>,
+    /// Results file path.
+    #[arg(long)]
+    pub output: Option<String>,
+    /// Per-request timeout in seconds.
+    #[arg(long)]
+    pub timeout: Option<u64>,
+    /// Base API endpoint.
+    #[arg(long)]
+    pub api_base: Option<String>,
+    /// `type` query parameter selecting the reward flow.
+    #[arg(long)]
+    pub api_type: Option<u32>,
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// A failed fetch, classified by whether retrying could plausibly help.
+///
+/// Permanent errors (4xx other than 429) fail fast so they don't burn the
+/// retry budget, while retryable errors (timeouts, connect errors, 5xx, 429)
+/// are retried — optionally honoring a server-provided `Retry-After` delay.
+#[derive(Debug)]
+pub enum FetchError {
+    Retryable {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    Permanent {
+        message: String,
+    },
+}
+
+impl FetchError {
+    pub fn retryable(message: impl Into<String>) -> Self {
+        FetchError::Retryable {
+            message: message.into(),
+            retry_after: None,
+        }
+    }
+
+    pub fn retryable_after(message: impl Into<String>, retry_after: Option<Duration>) -> Self {
+        FetchError::Retryable {
+            message: message.into(),
+            retry_after,
+        }
+    }
+
+    pub fn permanent(message: impl Into<String>) -> Self {
+        FetchError::Permanent {
+            message: message.into(),
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, FetchError::Retryable { .. })
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            FetchError::Retryable { retry_after, .. } => *retry_after,
+            FetchError::Permanent { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Retryable { message, .. } | FetchError::Permanent { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    /// Classify a transport-level reqwest error. Timeouts and connect errors
+    /// are transient; anything else is treated as retryable too since it
+    /// originates from the request machinery rather than a client mistake.
+    fn from(err: reqwest::Error) -> Self {
+        FetchError::retryable(err.to_string())
+    }
+}
+
+/// Parse a `Retry-After` header value, which is either delta-seconds or an
+/// HTTP-date, into a delay from now. Returns `None` if it can't be parsed or
+/// the date is already in the past.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let when = httpdate::parse_http_date(value).ok()?;
+    when.duration_since(SystemTime::now()).ok()
+}
@@ -1,9 +1,31 @@
+mod breaker;
+mod config;
+mod error;
+mod proxy;
+mod rate_limit;
+mod results;
+
+use clap::Parser;
 use futures::future::join_all;
 use reqwest::{Client, Proxy, redirect::Policy};
 use serde::Deserialize;
 use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::io::AsyncBufReadExt;
+use tokio::sync::Semaphore;
+
+use rand::Rng;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+
+use breaker::CircuitBreaker;
+use config::{Cli, Config};
+use error::{FetchError, parse_retry_after};
+use proxy::ProxyPool;
+use rate_limit::TokenBucket;
+use results::{AddressResult, ResultWriter, Status, load_checkpoint};
 
 #[derive(Debug, Deserialize)]
 struct Response {
@@ -15,35 +37,77 @@ struct Data {
     amount: f64,
 }
 
+/// Base backoff unit; doubled each attempt and capped by [`MAX_BACKOFF`].
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound on a single backoff sleep.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
 async fn get_airdrop_amount_with_retry(
     address: &str,
-    max_retries: u32,
-    proxy: Option<String>,
-) -> Result<f64, reqwest::Error> {
+    pool: &ProxyPool,
+    config: &Config,
+) -> Result<f64, FetchError> {
     let mut retries = 0;
     loop {
-        match get_airdrop_amount(address, proxy.clone()).await {
-            Ok(amount) => return Ok(amount),
-            Err(e) if retries < max_retries => {
+        // Draw a fresh proxy each attempt so a tripped breaker routes the
+        // retry to a different proxy instead of re-hammering the dead one.
+        let proxy = pool.select();
+        match get_airdrop_amount(address, proxy.clone(), pool, config).await {
+            Ok(amount) => {
+                if let Some(p) = &proxy {
+                    pool.breaker().record_success(p);
+                }
+                return Ok(amount);
+            }
+            Err(e) => {
+                if let Some(p) = &proxy {
+                    pool.breaker().record_failure(p);
+                }
+                // Permanent failures (e.g. a 4xx that isn't 429) can't be
+                // fixed by retrying, so fail fast without spending the budget.
+                if !e.is_retryable() || retries >= config.max_retries {
+                    return Err(e);
+                }
+                // Honor a server-provided Retry-After, otherwise back off
+                // exponentially with full jitter.
+                let delay = e.retry_after().unwrap_or_else(|| backoff_with_jitter(retries));
                 eprintln!(
-                    "Retry {}/{} for address {}: {}",
+                    "Retry {}/{} for address {} in {:?}: {}",
                     retries + 1,
-                    max_retries,
+                    config.max_retries,
                     address,
+                    delay,
                     e
                 );
                 retries += 1;
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                tokio::time::sleep(delay).await;
             }
-            Err(e) => return Err(e),
         }
     }
 }
 
-async fn get_airdrop_amount(address: &str, proxy: Option<String>) -> Result<f64, reqwest::Error> {
-    let mut client_builder = Client::builder().redirect(Policy::none());
+/// Exponential backoff with full jitter: a uniform random delay in
+/// `[0, min(MAX_BACKOFF, BASE_BACKOFF * 2^attempt)]`.
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let exp = BASE_BACKOFF
+        .as_millis()
+        .saturating_mul(1u128 << attempt.min(31));
+    let cap = exp.min(MAX_BACKOFF.as_millis()) as u64;
+    let jittered = rand::thread_rng().gen_range(0..=cap);
+    Duration::from_millis(jittered)
+}
 
-    if let Some(proxy_url) = proxy {
+async fn get_airdrop_amount(
+    address: &str,
+    proxy: Option<String>,
+    pool: &ProxyPool,
+    config: &Config,
+) -> Result<f64, FetchError> {
+    let mut client_builder = Client::builder()
+        .redirect(Policy::none())
+        .timeout(Duration::from_secs(config.request_timeout_secs));
+
+    if let Some(proxy_url) = &proxy {
         client_builder = client_builder.proxy(Proxy::all(format!("http://{}", proxy_url))?);
     }
 
@@ -55,10 +119,38 @@ async fn get_airdrop_amount(address: &str, proxy: Option<String>) -> Result<f64,
         .as_secs();
 
     let url = format!(
-        "https://opapi.kiloex.io/point/queryKiloAccountAwardFlow?type=0&account={}&t={}",
-        address, timestamp
+        "{}?type={}&account={}&t={}",
+        config.api_base, config.api_type, address, timestamp
     );
-    let res = client.get(url).send().await?.json::<Response>().await?;
+    let started = std::time::Instant::now();
+    let send_result = client.get(url).send().await;
+    if let Some(proxy_url) = &proxy {
+        pool.record_latency(proxy_url, started.elapsed());
+    }
+    let res = send_result?;
+    let status = res.status();
+
+    // Classify non-success responses so the retry layer can back off on
+    // transient failures and fail fast on permanent ones.
+    if !status.is_success() {
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            let retry_after = res
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(FetchError::retryable_after(
+                format!("HTTP {}", status),
+                retry_after,
+            ));
+        }
+        if status.is_server_error() {
+            return Err(FetchError::retryable(format!("HTTP {}", status)));
+        }
+        return Err(FetchError::permanent(format!("HTTP {}", status)));
+    }
+
+    let res = res.json::<Response>().await?;
 
     if res.data.is_empty() {
         return Ok(0.0);
@@ -86,12 +178,10 @@ pub async fn read_lines(path: impl AsRef<Path>) -> Result<Vec<String>, std::io::
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    const ADDRESSES_PATH_KEY: &str = "data/addresses.txt";
-    const PROXIES_PATH_KEY: &str = "data/proxies.txt";
-    const MAX_RETRIES: u32 = 10;
+async fn main() -> anyhow::Result<()> {
+    let config = Config::load(Cli::parse())?;
 
-    let addresses = match read_lines(ADDRESSES_PATH_KEY).await {
+    let addresses = match read_lines(&config.addresses_path).await {
         Ok(addresses) => addresses,
         Err(e) => {
             eprintln!("Error reading addresses file: {}", e);
@@ -99,32 +189,104 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let proxies = match read_lines(PROXIES_PATH_KEY).await {
+    let proxies = match read_lines(&config.proxies_path).await {
         Ok(proxies) if !proxies.is_empty() => proxies,
         _ => Vec::new(),
     };
 
-    let mut proxy_iter = proxies.into_iter().cycle();
+    // Resume: skip addresses already recorded in the results file.
+    let checkpoint = match load_checkpoint(&config.output_path, config.output_format) {
+        Ok(checkpoint) => checkpoint,
+        Err(e) => {
+            eprintln!("Error reading results file: {}", e);
+            return Ok(());
+        }
+    };
+    if !checkpoint.completed.is_empty() {
+        eprintln!(
+            "Resuming: {} addresses already completed, skipping them",
+            checkpoint.completed.len()
+        );
+    }
+    let pending: Vec<String> = addresses
+        .into_iter()
+        .filter(|address| !checkpoint.completed.contains(address))
+        .collect();
+
+    let writer = match ResultWriter::open(&config.output_path, config.output_format) {
+        Ok(writer) => Arc::new(Mutex::new(writer)),
+        Err(e) => {
+            eprintln!("Error opening results file: {}", e);
+            return Ok(());
+        }
+    };
+    // Running total, seeded with the amounts recovered from the checkpoint.
+    let total = Arc::new(Mutex::new(checkpoint.total));
+
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrency));
+    let bucket = TokenBucket::new(config.rate_per_sec, config.burst);
+    let breaker = CircuitBreaker::new(
+        config.failure_threshold,
+        Duration::from_secs(config.cooldown_secs),
+    );
+    let pool = ProxyPool::new(proxies, breaker);
+    if pool.is_empty() {
+        eprintln!("No proxies configured; sending requests directly");
+    }
+    let config = Arc::new(config);
 
-    let futures = addresses.iter().map(|address| {
-        let proxy = proxy_iter.next();
+    let futures = pending.iter().map(|address| {
+        let address = address.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let bucket = Arc::clone(&bucket);
+        let pool = pool.clone();
+        let writer = Arc::clone(&writer);
+        let total = Arc::clone(&total);
+        let config = Arc::clone(&config);
 
         async move {
-            match get_airdrop_amount_with_retry(address, MAX_RETRIES, proxy.clone()).await {
+            let _permit = semaphore.acquire_owned().await.ok()?;
+            bucket.acquire().await;
+
+            let result = match get_airdrop_amount_with_retry(&address, &pool, &config).await {
                 Ok(amount) => {
                     println!("Address {}: {} KILO", address, amount);
-                    Some(amount)
+                    *total.lock().unwrap() += amount;
+                    AddressResult {
+                        address: address.clone(),
+                        amount: Some(amount),
+                        status: Status::Ok,
+                        error: None,
+                    }
                 }
                 Err(e) => {
                     eprintln!("Failed after retries for address {}: {}", address, e);
-                    None
+                    AddressResult {
+                        address: address.clone(),
+                        amount: None,
+                        status: Status::Failed,
+                        error: Some(e.to_string()),
+                    }
                 }
+            };
+            if let Err(e) = writer.lock().unwrap().write(&result) {
+                eprintln!("Error writing result for {}: {}", address, e);
             }
+            Some(())
         }
     });
 
-    let results = join_all(futures).await;
-    let total_sum: f64 = results.into_iter().filter_map(|x| x).sum();
+    // Run to completion. On Ctrl-C, stop waiting on the in-flight queries
+    // (their results are lost) and report the partial total; every finished
+    // result has already been flushed by the writer.
+    tokio::select! {
+        _ = join_all(futures) => {}
+        _ = tokio::signal::ctrl_c() => {
+            eprintln!("\nInterrupted, flushing partial results...");
+        }
+    }
+
+    let total_sum = *total.lock().unwrap();
     println!("Total sum across all addresses: {} KILO", total_sum);
 
     Ok(())
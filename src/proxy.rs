@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::breaker::CircuitBreaker;
+
+/// Smoothing factor for the latency EWMA; higher reacts faster to new samples.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// The pool of proxies requests are routed through, paired with the shared
+/// circuit breaker so tripped proxies are skipped during selection and an
+/// exponentially-weighted latency estimate per proxy so load steers toward
+/// the faster ones.
+#[derive(Clone)]
+pub struct ProxyPool {
+    proxies: Arc<Vec<String>>,
+    latency: Arc<Mutex<HashMap<String, f64>>>,
+    breaker: CircuitBreaker,
+}
+
+impl ProxyPool {
+    pub fn new(proxies: Vec<String>, breaker: CircuitBreaker) -> Self {
+        ProxyPool {
+            proxies: Arc::new(proxies),
+            latency: Arc::new(Mutex::new(HashMap::new())),
+            breaker,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Current EWMA latency score for `proxy`. Never-probed proxies score 0 so
+    /// power-of-two-choices always prefers exploring them.
+    fn score(&self, proxy: &str) -> f64 {
+        *self.latency.lock().unwrap().get(proxy).unwrap_or(&0.0)
+    }
+
+    /// Fold a fresh latency sample into the proxy's EWMA score.
+    pub fn record_latency(&self, proxy: &str, sample: Duration) {
+        let sample_ms = sample.as_secs_f64() * 1000.0;
+        let mut latency = self.latency.lock().unwrap();
+        let entry = latency.entry(proxy.to_string()).or_insert(0.0);
+        *entry = EWMA_ALPHA * sample_ms + (1.0 - EWMA_ALPHA) * *entry;
+    }
+
+    /// Pick a proxy via power-of-two-choices: sample two proxies at random and
+    /// take the one with the lower latency score, skipping breakers that are
+    /// currently Open. Falls back to any proxy if both draws are tripped so a
+    /// request is still attempted rather than stalling.
+    pub fn select(&self) -> Option<String> {
+        let n = self.proxies.len();
+        if n == 0 {
+            return None;
+        }
+        let mut rng = rand::thread_rng();
+        let a = &self.proxies[rng.gen_range(0..n)];
+        let b = &self.proxies[rng.gen_range(0..n)];
+
+        let a_ok = self.breaker.allows(a);
+        let b_ok = self.breaker.allows(b);
+
+        let chosen = match (a_ok, b_ok) {
+            (true, true) => {
+                if self.score(a) <= self.score(b) {
+                    a
+                } else {
+                    b
+                }
+            }
+            (true, false) => a,
+            (false, true) => b,
+            (false, false) => &self.proxies[rng.gen_range(0..n)],
+        };
+        Some(chosen.clone())
+    }
+}
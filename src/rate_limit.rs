@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// A token-bucket rate limiter.
+///
+/// Tokens are refilled by a background task at `rate` per second up to a
+/// `burst` ceiling. A caller takes one token via [`TokenBucket::acquire`],
+/// decrementing the atomic counter when tokens are available or awaiting a
+/// refill notification otherwise. Combined with a concurrency semaphore this
+/// caps both the instantaneous in-flight requests and the sustained request
+/// rate against a single endpoint.
+pub struct TokenBucket {
+    tokens: AtomicU32,
+    burst: u32,
+    notify: Notify,
+}
+
+impl TokenBucket {
+    /// Build a bucket that refills `rate` tokens per second up to `burst` and
+    /// spawn its background refill task. The bucket starts full so the first
+    /// `burst` requests are admitted without waiting.
+    pub fn new(rate: u32, burst: u32) -> Arc<Self> {
+        let bucket = Arc::new(TokenBucket {
+            tokens: AtomicU32::new(burst),
+            burst,
+            notify: Notify::new(),
+        });
+
+        let refill = Arc::clone(&bucket);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                refill.add_tokens(rate);
+            }
+        });
+
+        bucket
+    }
+
+    /// Add up to `rate` tokens, saturating at `burst`, and wake waiters.
+    fn add_tokens(&self, rate: u32) {
+        let mut current = self.tokens.load(Ordering::Acquire);
+        loop {
+            let next = (current + rate).min(self.burst);
+            match self.tokens.compare_exchange_weak(
+                current,
+                next,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(observed) => current = observed,
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Take a single token, awaiting a refill if the bucket is empty.
+    pub async fn acquire(&self) {
+        loop {
+            if self.try_take() {
+                return;
+            }
+            // Register for a refill notification *before* the final emptiness
+            // check so a `notify_waiters()` between the check and the await
+            // can't be missed (it only wakes already-registered waiters).
+            let notified = self.notify.notified();
+            if self.try_take() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Attempt to decrement a single token without blocking.
+    fn try_take(&self) -> bool {
+        let mut current = self.tokens.load(Ordering::Acquire);
+        while current > 0 {
+            match self.tokens.compare_exchange_weak(
+                current,
+                current - 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(observed) => current = observed,
+            }
+        }
+        false
+    }
+}
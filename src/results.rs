@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of querying a single address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressResult {
+    pub address: String,
+    pub amount: Option<f64>,
+    pub status: Status,
+    pub error: Option<String>,
+}
+
+/// Whether an address was queried successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Failed,
+}
+
+/// Serialization format for the results file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// One JSON object per line (JSON Lines), appended incrementally.
+    Json,
+    /// RFC 4180 CSV with a header row.
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!("unknown output format: {}", other)),
+        }
+    }
+}
+
+/// Appends [`AddressResult`]s to a file as they complete so a crash or Ctrl-C
+/// only loses the in-flight queries, never the finished ones.
+pub struct ResultWriter {
+    file: File,
+    format: OutputFormat,
+}
+
+impl ResultWriter {
+    /// Open (or create) the results file for appending. A freshly created CSV
+    /// file gets its header row; existing files are appended to untouched so
+    /// reruns accumulate onto prior progress.
+    pub fn open(path: impl AsRef<Path>, format: OutputFormat) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let fresh = !path.exists() || std::fs::metadata(path)?.len() == 0;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if fresh && format == OutputFormat::Csv {
+            writeln!(file, "address,amount,status,error")?;
+            file.flush()?;
+        }
+        Ok(ResultWriter { file, format })
+    }
+
+    /// Append a single result and flush so it survives an abrupt exit.
+    pub fn write(&mut self, result: &AddressResult) -> std::io::Result<()> {
+        match self.format {
+            OutputFormat::Json => {
+                let line = serde_json::to_string(result)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                writeln!(self.file, "{}", line)?;
+            }
+            OutputFormat::Csv => {
+                writeln!(
+                    self.file,
+                    "{},{},{},{}",
+                    csv_field(&result.address),
+                    result.amount.map(|a| a.to_string()).unwrap_or_default(),
+                    match result.status {
+                        Status::Ok => "ok",
+                        Status::Failed => "failed",
+                    },
+                    csv_field(result.error.as_deref().unwrap_or(""))
+                )?;
+            }
+        }
+        self.file.flush()
+    }
+}
+
+/// Quote a CSV field if it contains a comma, quote, or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Progress recovered from an existing results file: the set of addresses
+/// already completed (to be skipped) and the total of their `ok` amounts.
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+    pub completed: HashSet<String>,
+    pub total: f64,
+}
+
+/// Read back an existing results file so a rerun resumes instead of
+/// re-querying. A missing file yields an empty checkpoint.
+pub fn load_checkpoint(path: impl AsRef<Path>, format: OutputFormat) -> std::io::Result<Checkpoint> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(Checkpoint::default());
+    }
+
+    let mut checkpoint = Checkpoint::default();
+    let reader = BufReader::new(File::open(path)?);
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match format {
+            OutputFormat::Json => {
+                if let Ok(result) = serde_json::from_str::<AddressResult>(&line) {
+                    record(&mut checkpoint, &result.address, result.amount, result.status);
+                }
+            }
+            OutputFormat::Csv => {
+                if idx == 0 && line.starts_with("address,") {
+                    continue;
+                }
+                let fields: Vec<&str> = line.splitn(4, ',').collect();
+                if fields.len() < 3 {
+                    continue;
+                }
+                let amount = fields[1].parse::<f64>().ok();
+                let status = if fields[2] == "ok" {
+                    Status::Ok
+                } else {
+                    Status::Failed
+                };
+                record(&mut checkpoint, fields[0], amount, status);
+            }
+        }
+    }
+    Ok(checkpoint)
+}
+
+fn record(checkpoint: &mut Checkpoint, address: &str, amount: Option<f64>, status: Status) {
+    // Only successes count as completed; failed addresses are left out of the
+    // skip set so a rerun retries the transient (proxy/429) failure tail.
+    if status == Status::Ok {
+        checkpoint.completed.insert(address.to_string());
+        checkpoint.total += amount.unwrap_or(0.0);
+    }
+}